@@ -0,0 +1,409 @@
+//! CUDA Array (pitched, texture-friendly) memory
+
+use super::*;
+use crate::*;
+use cuda::*;
+use std::{marker::PhantomData, sync::Arc};
+
+/// Dimensions of an [ArrayMemory] allocation.
+///
+/// `Array1D`/`Array2D` are backed by [cuArrayCreate]; `Array3D` uses
+/// [cuArray3DCreate].
+///
+/// [cuArrayCreate]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g03b3ca7f2dde63d90e43d37b22a6b94c
+/// [cuArray3DCreate]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1gc2a72d90c0f13d9e7d1ddcfaf8742e1c
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayShape {
+    Array1D { width: usize },
+    Array2D { width: usize, height: usize },
+    Array3D {
+        width: usize,
+        height: usize,
+        depth: usize,
+    },
+}
+
+impl ArrayShape {
+    fn height(&self) -> usize {
+        match self {
+            ArrayShape::Array1D { .. } => 0,
+            ArrayShape::Array2D { height, .. } => *height,
+            ArrayShape::Array3D { height, .. } => *height,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            ArrayShape::Array1D { .. } | ArrayShape::Array2D { .. } => 0,
+            ArrayShape::Array3D { depth, .. } => *depth,
+        }
+    }
+
+    fn width(&self) -> usize {
+        match self {
+            ArrayShape::Array1D { width }
+            | ArrayShape::Array2D { width, .. }
+            | ArrayShape::Array3D { width, .. } => *width,
+        }
+    }
+
+    /// Total number of elements over all dimensions.
+    pub fn num_elem(&self) -> usize {
+        match self {
+            ArrayShape::Array1D { width } => *width,
+            ArrayShape::Array2D { width, height } => width * height,
+            ArrayShape::Array3D {
+                width,
+                height,
+                depth,
+            } => width * height * depth,
+        }
+    }
+}
+
+/// Map a [Scalar] element type onto a CUDA array channel format and channel
+/// count.
+///
+/// Only the element *width* is used: signed and floating-point element types
+/// get the unsigned-integer format of matching width, and
+/// [ArrayMemory::create_texture_object] sets no read-mode flags on top of
+/// that format. 8-byte elements have no direct
+/// single-channel format, so they're represented as a 2-channel 32-bit
+/// format instead (the same trick CUDA's own `int2`/`float2` vector types
+/// use) -- `WidthInBytes`/`Pitch` are unaffected since those count total
+/// bytes per element regardless of channel count.
+fn array_format<T: Scalar>() -> (CUarray_format, u32) {
+    match T::size_of() {
+        1 => (CUarray_format_enum::CU_AD_FORMAT_UNSIGNED_INT8, 1),
+        2 => (CUarray_format_enum::CU_AD_FORMAT_UNSIGNED_INT16, 1),
+        4 => (CUarray_format_enum::CU_AD_FORMAT_UNSIGNED_INT32, 1),
+        8 => (CUarray_format_enum::CU_AD_FORMAT_UNSIGNED_INT32, 2),
+        bytes => unimplemented!("No CUDA array format for {}-byte elements", bytes),
+    }
+}
+
+/// Memory allocated as a CUDA Array, i.e. pitched/tiled memory optimized for
+/// texture and surface reads rather than plain global-memory addressing.
+///
+/// Backed by [cuArrayCreate]/[cuArray3DCreate], freed with [cuArrayDestroy]
+/// on drop. Unlike [DeviceMemory], it cannot be addressed as flat `[T]`; data
+/// moves in and out via [cuMemcpy2D_v2]/[cuMemcpy3D_v2].
+///
+/// [cuArrayCreate]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g03b3ca7f2dde63d90e43d37b22a6b94c
+/// [cuArray3DCreate]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1gc2a72d90c0f13d9e7d1ddcfaf8742e1c
+/// [cuArrayDestroy]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g3c1b9dadd23c1f4edf7e4bb35c67ef4f
+/// [cuMemcpy2D_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g0e4a84c1c0e36b1b0994cb4a3a79d2e6
+/// [cuMemcpy3D_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g9cad7239d7612b173b35e5c4be2471a0
+pub struct ArrayMemory<T> {
+    array: CUarray,
+    shape: ArrayShape,
+    context: Arc<Context>,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Drop for ArrayMemory<T> {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe { contexted_call!(self, cuArrayDestroy, self.array) } {
+            log::error!("Failed to free CUDA array memory: {:?}", e);
+        }
+    }
+}
+
+impl<T> Contexted for ArrayMemory<T> {
+    fn get_context(&self) -> Arc<Context> {
+        self.context.clone()
+    }
+}
+
+impl<T: Scalar> Memory for ArrayMemory<T> {
+    type Elem = T;
+    // `CUarray` is an opaque handle into pitched/tiled storage, not a linear
+    // device pointer -- there is no address that could stand in for it here,
+    // so unlike every other `Memory` impl these are not meant to be called.
+    // Array transfers route through `copy_from_linear`/`copy_from_array`,
+    // which use the `array` handle directly instead of going through `Memory`.
+    fn head_addr(&self) -> *const T {
+        unreachable!("ArrayMemory has no linear data pointer; use copy_from_linear/copy_from_array")
+    }
+
+    fn head_addr_mut(&mut self) -> *mut T {
+        unreachable!("ArrayMemory has no linear data pointer; use copy_from_linear/copy_from_array")
+    }
+
+    fn num_elem(&self) -> usize {
+        self.shape.num_elem()
+    }
+
+    fn memory_type(&self) -> MemoryType {
+        MemoryType::Array
+    }
+
+    fn try_as_slice(&self) -> Option<&[T]> {
+        None
+    }
+
+    fn try_as_mut_slice(&mut self) -> Option<&mut [T]> {
+        None
+    }
+
+    fn try_get_context(&self) -> Option<Arc<Context>> {
+        Some(self.get_context())
+    }
+}
+
+impl<T: Scalar> Allocatable for ArrayMemory<T> {
+    type Shape = ArrayShape;
+    unsafe fn uninitialized(context: Arc<Context>, shape: ArrayShape) -> Self {
+        assert!(shape.num_elem() > 0, "Zero-sized malloc is forbidden");
+        let (format, num_channels) = array_format::<T>();
+        let array = match shape {
+            ArrayShape::Array1D { width } => {
+                let descriptor = CUDA_ARRAY_DESCRIPTOR {
+                    Width: width,
+                    Height: 1,
+                    Format: format,
+                    NumChannels: num_channels,
+                };
+                contexted_new!(&context, cuArrayCreate_v2, &descriptor)
+            }
+            ArrayShape::Array2D { width, height } => {
+                let descriptor = CUDA_ARRAY_DESCRIPTOR {
+                    Width: width,
+                    Height: height,
+                    Format: format,
+                    NumChannels: num_channels,
+                };
+                contexted_new!(&context, cuArrayCreate_v2, &descriptor)
+            }
+            ArrayShape::Array3D {
+                width,
+                height,
+                depth,
+            } => {
+                let descriptor = CUDA_ARRAY3D_DESCRIPTOR {
+                    Width: width,
+                    Height: height,
+                    Depth: depth,
+                    Format: format,
+                    NumChannels: num_channels,
+                    Flags: 0,
+                };
+                contexted_new!(&context, cuArray3DCreate_v2, &descriptor)
+            }
+        }
+        .expect("Cannot allocate CUDA array memory");
+        ArrayMemory {
+            array,
+            shape,
+            context,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Scalar> ArrayMemory<T> {
+    /// Shape this array was allocated with.
+    pub fn shape(&self) -> ArrayShape {
+        self.shape
+    }
+
+    fn pitch_bytes(&self) -> usize {
+        self.shape.width() * std::mem::size_of::<T>()
+    }
+
+    /// Copy from linear device/host memory into this array.
+    ///
+    /// `src` must hold exactly `self.num_elem()` elements laid out row-major.
+    pub fn copy_from_linear<Src: Memory<Elem = T> + Continuous>(&mut self, src: &Src) {
+        assert_eq!(self.num_elem(), src.num_elem());
+        let ctx = self.context.clone();
+        let _g = ctx.guard_context();
+        match self.shape {
+            ArrayShape::Array1D { width } | ArrayShape::Array2D { width, .. } => {
+                let height = self.shape.height().max(1);
+                let copy = CUDA_MEMCPY2D {
+                    srcMemoryType: cuda_memory_type(src.memory_type()),
+                    srcHost: src.head_addr() as _,
+                    srcDevice: src.head_addr() as _,
+                    srcPitch: width * std::mem::size_of::<T>(),
+                    dstMemoryType: CUmemorytype_enum::CU_MEMORYTYPE_ARRAY,
+                    dstArray: self.array,
+                    WidthInBytes: self.pitch_bytes(),
+                    Height: height,
+                    ..Default::default()
+                };
+                ffi_call!(cuMemcpy2D_v2, &copy).expect("memcpy into CUDA array failed");
+            }
+            ArrayShape::Array3D {
+                width,
+                height,
+                depth,
+            } => {
+                let copy = CUDA_MEMCPY3D {
+                    srcMemoryType: cuda_memory_type(src.memory_type()),
+                    srcHost: src.head_addr() as _,
+                    srcDevice: src.head_addr() as _,
+                    srcPitch: width * std::mem::size_of::<T>(),
+                    srcHeight: height,
+                    dstMemoryType: CUmemorytype_enum::CU_MEMORYTYPE_ARRAY,
+                    dstArray: self.array,
+                    WidthInBytes: self.pitch_bytes(),
+                    Height: height,
+                    Depth: depth,
+                    ..Default::default()
+                };
+                ffi_call!(cuMemcpy3D_v2, &copy).expect("memcpy into CUDA array failed");
+            }
+        }
+    }
+
+    /// Bind this array to a texture object for use in a kernel.
+    ///
+    /// The returned [TextureObject] borrows this array and destroys the
+    /// texture object on drop, so it cannot outlive the [ArrayMemory] it
+    /// was created from.
+    ///
+    /// See also [cuTexObjectCreate].
+    ///
+    /// [cuTexObjectCreate]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html#group__CUDA__TEXOBJECT_1gc305b48ad10ce3ffc6e2c50b8f34d6c1
+    pub fn create_texture_object(
+        &self,
+        address_mode: CUaddress_mode,
+        filter_mode: CUfilter_mode,
+    ) -> TextureObject<'_, T> {
+        let resource = CUDA_RESOURCE_DESC {
+            resType: CUresourcetype_enum::CU_RESOURCE_TYPE_ARRAY,
+            res: CUDA_RESOURCE_DESC_st__bindgen_ty_1 {
+                array: CUDA_RESOURCE_DESC_st__bindgen_ty_1__bindgen_ty_1 {
+                    hArray: self.array,
+                },
+            },
+            flags: 0,
+        };
+        let texture = CUDA_TEXTURE_DESC {
+            addressMode: [address_mode; 3],
+            filterMode: filter_mode,
+            flags: 0,
+            ..Default::default()
+        };
+        let texture = contexted_new!(
+            self,
+            cuTexObjectCreate,
+            &resource,
+            &texture,
+            std::ptr::null()
+        )
+        .expect("Cannot create texture object");
+        TextureObject {
+            texture,
+            context: self.context.clone(),
+            array: PhantomData,
+        }
+    }
+}
+
+/// A texture object bound to an [ArrayMemory], destroyed with
+/// [cuTexObjectDestroy] on drop.
+///
+/// [cuTexObjectDestroy]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html#group__CUDA__TEXOBJECT_1g965c0c93ed5e3c4a29a1dd8bf8b7f9bd
+pub struct TextureObject<'a, T> {
+    texture: CUtexObject,
+    context: Arc<Context>,
+    array: PhantomData<&'a ArrayMemory<T>>,
+}
+
+impl<'a, T> TextureObject<'a, T> {
+    /// The raw handle, for passing into a kernel launch.
+    pub fn as_raw(&self) -> CUtexObject {
+        self.texture
+    }
+}
+
+impl<'a, T> Contexted for TextureObject<'a, T> {
+    fn get_context(&self) -> Arc<Context> {
+        self.context.clone()
+    }
+}
+
+impl<'a, T> Drop for TextureObject<'a, T> {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe { contexted_call!(self, cuTexObjectDestroy, self.texture) } {
+            log::error!("Failed to destroy CUDA texture object: {:?}", e);
+        }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceMemory<T>> for ArrayMemory<T> {
+    fn copy_from(&mut self, src: &DeviceMemory<T>) {
+        self.copy_from_linear(src)
+    }
+}
+
+impl<T: Scalar> Memcpy<PageLockedMemory<T>> for ArrayMemory<T> {
+    fn copy_from(&mut self, src: &PageLockedMemory<T>) {
+        self.copy_from_linear(src)
+    }
+}
+
+/// Copy from a CUDA array back into linear device/host memory.
+///
+/// Safety
+/// ------
+/// - `dest` must have exactly `src.num_elem()` elements of storage (checked;
+///   panics otherwise)
+pub(super) unsafe fn copy_from_array<T: Scalar, Dest>(dest: &mut Dest, src: &ArrayMemory<T>)
+where
+    Dest: Memory<Elem = T> + Continuous + ?Sized,
+{
+    assert_eq!(dest.num_elem(), src.num_elem());
+    let ctx = src.context.clone();
+    let _g = ctx.guard_context();
+    let dst_ty = cuda_memory_type(dest.memory_type());
+    match src.shape {
+        ArrayShape::Array1D { width } | ArrayShape::Array2D { width, .. } => {
+            let height = src.shape.height().max(1);
+            let copy = CUDA_MEMCPY2D {
+                srcMemoryType: CUmemorytype_enum::CU_MEMORYTYPE_ARRAY,
+                srcArray: src.array,
+                dstMemoryType: dst_ty,
+                dstHost: dest.head_addr_mut() as _,
+                dstDevice: dest.head_addr_mut() as _,
+                dstPitch: width * std::mem::size_of::<T>(),
+                WidthInBytes: src.pitch_bytes(),
+                Height: height,
+                ..Default::default()
+            };
+            ffi_call!(cuMemcpy2D_v2, &copy).expect("memcpy from CUDA array failed");
+        }
+        ArrayShape::Array3D {
+            width,
+            height,
+            depth,
+        } => {
+            let copy = CUDA_MEMCPY3D {
+                srcMemoryType: CUmemorytype_enum::CU_MEMORYTYPE_ARRAY,
+                srcArray: src.array,
+                dstMemoryType: dst_ty,
+                dstHost: dest.head_addr_mut() as _,
+                dstDevice: dest.head_addr_mut() as _,
+                dstPitch: width * std::mem::size_of::<T>(),
+                dstHeight: height,
+                WidthInBytes: src.pitch_bytes(),
+                Height: height,
+                Depth: depth,
+                ..Default::default()
+            };
+            ffi_call!(cuMemcpy3D_v2, &copy).expect("memcpy from CUDA array failed");
+        }
+    }
+}
+
+fn cuda_memory_type(ty: MemoryType) -> CUmemorytype {
+    match ty {
+        MemoryType::Host | MemoryType::Registered | MemoryType::PageLocked => {
+            CUmemorytype_enum::CU_MEMORYTYPE_HOST
+        }
+        MemoryType::Device => CUmemorytype_enum::CU_MEMORYTYPE_DEVICE,
+        MemoryType::Array => CUmemorytype_enum::CU_MEMORYTYPE_ARRAY,
+    }
+}
@@ -1,10 +1,11 @@
 //! Device and Host memory handlers
 
-use super::*;
+use super::{array, *};
 use crate::*;
 use cuda::*;
 use std::{
     marker::PhantomData,
+    mem::size_of,
     ops::{Deref, DerefMut},
     sync::Arc,
 };
@@ -106,6 +107,56 @@ where
     Ok(())
 }
 
+/// Copy device memory living in one context to device memory living in
+/// another, falling back to a staging host round-trip when the two devices
+/// have no peer-to-peer link.
+///
+/// See also [cuMemcpyPeer] and [cuCtxEnablePeerAccess].
+///
+/// [cuMemcpyPeer]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PEER__ACCESS.html#group__CUDA__PEER__ACCESS_1g82fcecb38018e64b98616a8ac30112f2
+/// [cuCtxEnablePeerAccess]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PEER__ACCESS.html#group__CUDA__PEER__ACCESS_1g0889ec6728e61c05ed359551d67b3f5a
+unsafe fn peer_copy_to_device<T>(
+    d_ctx: &Arc<Context>,
+    dest_ptr: *mut T,
+    s_ctx: &Arc<Context>,
+    src_ptr: *const T,
+    bytes: usize,
+) {
+    let d_dev = contexted_new!(d_ctx, cuCtxGetDevice).expect("Cannot get device of context");
+    let s_dev = contexted_new!(s_ctx, cuCtxGetDevice).expect("Cannot get device of context");
+    let can_access: i32 = contexted_new!(d_ctx, cuDeviceCanAccessPeer, d_dev, s_dev).unwrap_or(0);
+    if can_access != 0 {
+        // Enabling twice returns CUDA_ERROR_PEER_ACCESS_ALREADY_ENABLED; we
+        // only care that access ends up enabled, so the error is ignored.
+        let _ = contexted_call!(d_ctx, cuCtxEnablePeerAccess, s_ctx.as_raw(), 0);
+        contexted_call!(
+            d_ctx,
+            cuMemcpyPeer,
+            dest_ptr as _,
+            d_ctx.as_raw(),
+            src_ptr as _,
+            s_ctx.as_raw(),
+            bytes
+        )
+        .expect("Peer-to-peer memcpy failed");
+    } else {
+        // No P2P link between the two devices: stage through pinned host memory.
+        let staging =
+            contexted_new!(s_ctx, cuMemAllocHost_v2, bytes).expect("Cannot allocate staging buffer");
+        {
+            let _g = s_ctx.guard_context();
+            ffi_call!(cuMemcpyDtoH_v2, staging, src_ptr as _, bytes)
+                .expect("Staging copy from source device failed");
+        }
+        {
+            let _g = d_ctx.guard_context();
+            ffi_call!(cuMemcpyHtoD_v2, dest_ptr as _, staging, bytes)
+                .expect("Staging copy to destination device failed");
+        }
+        contexted_call!(s_ctx, cuMemFreeHost, staging).expect("Cannot free staging buffer");
+    }
+}
+
 /// Safety
 /// ------
 /// - This works only when `dest` is device memory
@@ -121,6 +172,21 @@ where
     let dest_ptr = dest.head_addr_mut();
     let src_ptr = src.head_addr();
 
+    // Peer-to-peer: src is device memory living in a different context.
+    if src.memory_type() == MemoryType::Device {
+        if let (Some(d_ctx), Some(s_ctx)) = (dest.try_get_context(), src.try_get_context()) {
+            if d_ctx != s_ctx {
+                return peer_copy_to_device(
+                    &d_ctx,
+                    dest_ptr,
+                    &s_ctx,
+                    src_ptr,
+                    dest.num_elem() * std::mem::size_of::<T>(),
+                );
+            }
+        }
+    }
+
     // context guard
     let _g = match (dest.try_get_context(), src.try_get_context()) {
         (Some(d_ctx), Some(s_ctx)) => {
@@ -154,7 +220,16 @@ where
             .expect("memcpy from Device to Device failed");
         }
         // From array
-        MemoryType::Array => unimplemented!("Array memory is not supported yet"),
+        //
+        // The `Memcpy<ArrayMemory<T>>` impls (e.g. `DeviceMemory`'s) call
+        // `array::copy_from_array` directly rather than going through this
+        // generic dispatch, so a concrete `ArrayMemory` source never reaches
+        // here; this arm only guards against a hypothetical `dyn Memory`
+        // source reporting `MemoryType::Array`, which this function has no
+        // way to downcast into an `ArrayMemory` to copy from.
+        MemoryType::Array => unreachable!(
+            "Array copies must go through ArrayMemory's typed Memcpy impls"
+        ),
     }
 }
 
@@ -170,12 +245,145 @@ impl<T: Scalar> Memcpy<PageLockedMemory<T>> for DeviceMemory<T> {
     }
 }
 
+impl<T: Scalar> Memcpy<ArrayMemory<T>> for DeviceMemory<T> {
+    fn copy_from(&mut self, src: &ArrayMemory<T>) {
+        unsafe { array::copy_from_array(self, src) }
+    }
+}
+
 impl<T: Scalar> Memcpy<[T]> for DeviceMemory<T> {
     fn copy_from(&mut self, src: &[T]) {
         unsafe { copy_to_device(self, src) }
     }
 }
 
+impl<T: Scalar> DeviceMemory<T> {
+    /// Copy from pinned host memory to this buffer asynchronously on `stream`.
+    ///
+    /// Only [PageLockedMemory] is accepted as the source because async
+    /// H2D transfers only behave asynchronously when the host buffer is
+    /// pinned; pageable memory would force the driver to stage the copy
+    /// synchronously anyway.
+    ///
+    /// See also [cuMemcpyHtoDAsync_v2].
+    ///
+    /// [cuMemcpyHtoDAsync_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g56f30236c7c5247f8e061b59d3268362
+    pub fn copy_from_async<'a>(
+        &'a mut self,
+        src: &'a PageLockedMemory<T>,
+        stream: &'a Stream,
+    ) -> Transfer<'a, Self, PageLockedMemory<T>> {
+        assert_eq!(self.num_elem(), src.num_elem());
+        let ctx = self.get_context();
+        unsafe {
+            contexted_call!(
+                &ctx,
+                cuMemcpyHtoDAsync_v2,
+                self.ptr,
+                src.head_addr() as _,
+                self.size * size_of::<T>(),
+                stream.as_raw()
+            )
+        }
+        .expect("async memcpy from Host to Device failed");
+        Transfer {
+            stream,
+            dest: self,
+            src,
+        }
+    }
+
+    /// Copy from another device buffer to this one asynchronously on `stream`.
+    ///
+    /// See also [cuMemcpyDtoDAsync_v2].
+    ///
+    /// [cuMemcpyDtoDAsync_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html
+    pub fn copy_from_device_async<'a>(
+        &'a mut self,
+        src: &'a Self,
+        stream: &'a Stream,
+    ) -> Transfer<'a, Self, Self> {
+        assert_eq!(self.num_elem(), src.num_elem());
+        let ctx = self.get_context();
+        unsafe {
+            contexted_call!(
+                &ctx,
+                cuMemcpyDtoDAsync_v2,
+                self.ptr,
+                src.ptr,
+                self.size * size_of::<T>(),
+                stream.as_raw()
+            )
+        }
+        .expect("async memcpy from Device to Device failed");
+        Transfer {
+            stream,
+            dest: self,
+            src,
+        }
+    }
+
+    /// Migrate this managed allocation to `device` ahead of a kernel launch.
+    ///
+    /// See also [cuMemPrefetchAsync].
+    ///
+    /// [cuMemPrefetchAsync]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html
+    pub fn prefetch_to_device(&self, device: CUdevice, stream: &Stream) {
+        unsafe {
+            contexted_call!(
+                self,
+                cuMemPrefetchAsync,
+                self.ptr,
+                self.size * size_of::<T>(),
+                device,
+                stream.as_raw()
+            )
+        }
+        .expect("Cannot prefetch managed memory to device");
+    }
+
+    /// Migrate this managed allocation back to the host, e.g. before reading
+    /// results on the CPU.
+    ///
+    /// See also [cuMemPrefetchAsync].
+    ///
+    /// [cuMemPrefetchAsync]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html
+    pub fn prefetch_to_host(&self, stream: &Stream) {
+        unsafe {
+            contexted_call!(
+                self,
+                cuMemPrefetchAsync,
+                self.ptr,
+                self.size * size_of::<T>(),
+                CU_DEVICE_CPU,
+                stream.as_raw()
+            )
+        }
+        .expect("Cannot prefetch managed memory to host");
+    }
+
+    /// Hint the driver about how this managed allocation will be accessed,
+    /// e.g. `CU_MEM_ADVISE_SET_READ_MOSTLY` or
+    /// `CU_MEM_ADVISE_SET_PREFERRED_LOCATION`.
+    ///
+    /// See also [cuMemAdvise].
+    ///
+    /// [cuMemAdvise]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html
+    pub fn advise(&self, advice: CUmem_advise_enum, device: CUdevice) {
+        unsafe {
+            contexted_call!(
+                self,
+                cuMemAdvise,
+                self.ptr,
+                self.size * size_of::<T>(),
+                advice,
+                device
+            )
+        }
+        .expect("Cannot set memory advise");
+    }
+}
+
 impl<T: Scalar> Memset for DeviceMemory<T> {
     fn set(&mut self, value: Self::Elem) {
         unsafe { memset_device(self, value).expect("memset failed") };
@@ -219,6 +427,142 @@ impl<T: Scalar> Allocatable for DeviceMemory<T> {
     }
 }
 
+/// Plain, non-managed memory allocated on the device.
+///
+/// Unlike [DeviceMemory], this is backed by [cuMemAlloc_v2] rather than
+/// `cuMemAllocManaged`, so it never participates in Unified Memory migration
+/// and cannot silently page-fault during a kernel launch. The tradeoff is
+/// that it has no host-accessible view: `try_as_slice`/`try_as_mut_slice`
+/// always return `None`, and there is no `Deref` to `[T]`.
+///
+/// [cuMemAlloc_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1gb82d2a09844a58dd9e744dc31e8aa467
+pub struct DeviceBuffer<T> {
+    ptr: CUdeviceptr,
+    size: usize,
+    context: Arc<Context>,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Drop for DeviceBuffer<T> {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe { contexted_call!(self, cuMemFree_v2, self.ptr) } {
+            log::error!("Failed to free device memory: {:?}", e);
+        }
+    }
+}
+
+impl<T> Contexted for DeviceBuffer<T> {
+    fn get_context(&self) -> Arc<Context> {
+        self.context.clone()
+    }
+}
+
+impl<T: Scalar> Memory for DeviceBuffer<T> {
+    type Elem = T;
+    fn head_addr(&self) -> *const T {
+        self.ptr as _
+    }
+
+    fn head_addr_mut(&mut self) -> *mut T {
+        self.ptr as _
+    }
+
+    fn num_elem(&self) -> usize {
+        self.size
+    }
+
+    fn memory_type(&self) -> MemoryType {
+        MemoryType::Device
+    }
+
+    fn try_as_slice(&self) -> Option<&[T]> {
+        None
+    }
+
+    fn try_as_mut_slice(&mut self) -> Option<&mut [T]> {
+        None
+    }
+
+    fn try_get_context(&self) -> Option<Arc<Context>> {
+        Some(self.get_context())
+    }
+}
+
+impl<T: Scalar> Memcpy<Self> for DeviceBuffer<T> {
+    fn copy_from(&mut self, src: &Self) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceMemory<T>> for DeviceBuffer<T> {
+    fn copy_from(&mut self, src: &DeviceMemory<T>) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<PageLockedMemory<T>> for DeviceBuffer<T> {
+    fn copy_from(&mut self, src: &PageLockedMemory<T>) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<[T]> for DeviceBuffer<T> {
+    fn copy_from(&mut self, src: &[T]) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memset for DeviceBuffer<T> {
+    fn set(&mut self, value: Self::Elem) {
+        // No host-accessible fallback exists for this allocation mode. The
+        // driver only offers 1/2/4-byte memset primitives, so widths outside
+        // that set (e.g. f64) fall back to a byte-wise cuMemsetD8_v2 over the
+        // whole allocation -- which is exact whenever every byte of `value`'s
+        // representation is the same, as it always is for `zeros()`.
+        let elem_size = T::size_of();
+        let ptr = self.head_addr_mut() as _;
+        let size = self.num_elem();
+        let ctx = self.get_context();
+        unsafe {
+            match elem_size {
+                1 => contexted_call!(&ctx, cuMemsetD8_v2, ptr, value.to_le_u8().unwrap(), size),
+                2 => contexted_call!(&ctx, cuMemsetD16_v2, ptr, value.to_le_u16().unwrap(), size),
+                4 => contexted_call!(&ctx, cuMemsetD32_v2, ptr, value.to_le_u32().unwrap(), size),
+                _ => {
+                    let raw = std::slice::from_raw_parts(
+                        &value as *const T as *const u8,
+                        elem_size,
+                    );
+                    let byte = raw[0];
+                    assert!(
+                        raw.iter().all(|b| *b == byte),
+                        "Cannot memset {}-byte elements to a non-uniform byte pattern on a \
+                         non-host-accessible DeviceBuffer",
+                        elem_size
+                    );
+                    contexted_call!(&ctx, cuMemsetD8_v2, ptr, byte, size * elem_size)
+                }
+            }
+        }
+        .expect("memset failed");
+    }
+}
+
+impl<T: Scalar> Allocatable for DeviceBuffer<T> {
+    type Shape = usize;
+    unsafe fn uninitialized(context: Arc<Context>, size: usize) -> Self {
+        assert!(size > 0, "Zero-sized malloc is forbidden");
+        let ptr = contexted_new!(&context, cuMemAlloc_v2, size * std::mem::size_of::<T>())
+            .expect("Cannot allocate device memory");
+        DeviceBuffer {
+            ptr,
+            size,
+            context,
+            phantom: PhantomData,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,4 +586,14 @@ mod tests {
         let ctx = device.create_context();
         let _a = DeviceMemory::<i32>::zeros(ctx, 0);
     }
+
+    #[test]
+    fn device_buffer() -> Result<()> {
+        let device = Device::nth(0)?;
+        let ctx = device.create_context();
+        let mem = DeviceBuffer::<i32>::zeros(ctx, 12);
+        assert_eq!(mem.num_elem(), 12);
+        assert!(mem.try_as_slice().is_none());
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 //! Device and Host memory handlers
 
-use super::*;
+use super::{array, device::copy_to_device, *};
 use crate::*;
 use cuda::*;
 use std::{
@@ -104,13 +104,15 @@ where
             let dest_ptr = dest.head_addr_mut();
             let src_ptr = src.head_addr();
             // context guard
-            let _g = match (dest.try_get_context(), src.try_get_context()) {
-                (Some(d_ctx), Some(s_ctx)) => {
-                    assert_eq!(d_ctx, s_ctx);
-                    Some(d_ctx.guard_context())
-                }
-                (Some(ctx), None) => Some(ctx.guard_context()),
-                (None, Some(ctx)) => Some(ctx.guard_context()),
+            //
+            // Unlike device-to-device copies, `dest` here is host memory and
+            // is accessible regardless of which context is current, so only
+            // `src`'s (the device allocation's) context needs to be current.
+            // This also makes D2H copies work across devices/contexts without
+            // a peer-access path, unlike `copy_to_device`.
+            let _g = match (src.try_get_context(), dest.try_get_context()) {
+                (Some(s_ctx), _) => Some(s_ctx.guard_context()),
+                (None, Some(d_ctx)) => Some(d_ctx.guard_context()),
                 (None, None) => None,
             };
             unsafe {
@@ -124,7 +126,16 @@ where
             .expect("memcpy from Device to Host failed");
         }
         // From array
-        MemoryType::Array => unimplemented!("Array memory is not supported yet"),
+        //
+        // The `Memcpy<ArrayMemory<T>>` impls (e.g. `PageLockedMemory`'s) call
+        // `array::copy_from_array` directly rather than going through this
+        // generic dispatch, so a concrete `ArrayMemory` source never reaches
+        // here; this arm only guards against a hypothetical `dyn Memory`
+        // source reporting `MemoryType::Array`, which this function has no
+        // way to downcast into an `ArrayMemory` to copy from.
+        MemoryType::Array => unreachable!(
+            "Array copies must go through ArrayMemory's typed Memcpy impls"
+        ),
     }
 }
 
@@ -146,6 +157,49 @@ impl<T: Scalar> Memcpy<DeviceMemory<T>> for PageLockedMemory<T> {
     }
 }
 
+impl<T: Scalar> Memcpy<ArrayMemory<T>> for PageLockedMemory<T> {
+    fn copy_from(&mut self, src: &ArrayMemory<T>) {
+        unsafe { array::copy_from_array(self, src) }
+    }
+}
+
+impl<T: Scalar> PageLockedMemory<T> {
+    /// Copy from device memory into this pinned buffer asynchronously on `stream`.
+    ///
+    /// Only pinned host memory is accepted as the destination because async
+    /// D2H transfers only behave asynchronously when the host buffer is
+    /// pinned; a pageable `[T]` destination would force a synchronous
+    /// staging copy anyway.
+    ///
+    /// See also [cuMemcpyDtoHAsync_v2].
+    ///
+    /// [cuMemcpyDtoHAsync_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html
+    pub fn copy_from_async<'a>(
+        &'a mut self,
+        src: &'a DeviceMemory<T>,
+        stream: &'a Stream,
+    ) -> Transfer<'a, Self, DeviceMemory<T>> {
+        assert_eq!(self.num_elem(), src.num_elem());
+        let ctx = src.get_context();
+        unsafe {
+            contexted_call!(
+                &ctx,
+                cuMemcpyDtoHAsync_v2,
+                self.ptr as _,
+                src.head_addr() as _,
+                self.size * std::mem::size_of::<T>(),
+                stream.as_raw()
+            )
+        }
+        .expect("async memcpy from Device to Host failed");
+        Transfer {
+            stream,
+            dest: self,
+            src,
+        }
+    }
+}
+
 impl<T: Scalar> Memset for PageLockedMemory<T> {
     fn set(&mut self, value: Self::Elem) {
         self.iter_mut().for_each(|v| *v = value);
@@ -177,6 +231,121 @@ impl<T: Scalar> Allocatable for PageLockedMemory<T> {
     }
 }
 
+/// Host memory pinned in place by registering an existing buffer, rather
+/// than allocating a fresh one.
+///
+/// Wraps [cuMemHostRegister_v2] around a caller-owned `&'a mut [T]` (e.g. a
+/// `Vec` the caller already owns) and unregisters it with [cuMemHostUnregister]
+/// on drop, giving pinned-transfer speed without the copy into a separate
+/// staging [PageLockedMemory] buffer.
+///
+/// [cuMemHostRegister_v2]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1gf0a9fe11544326dabd743b7aa6b54223
+/// [cuMemHostUnregister]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g63f450c8125359be87b7623b1c0b2a14
+pub struct RegisteredMemory<'a, T> {
+    slice: &'a mut [T],
+    context: Arc<Context>,
+}
+
+impl<'a, T> Drop for RegisteredMemory<'a, T> {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe {
+            contexted_call!(self, cuMemHostUnregister, self.slice.as_mut_ptr() as *mut _)
+        } {
+            log::error!("Cannot unregister host memory: {:?}", e);
+        }
+    }
+}
+
+impl<'a, T> Contexted for RegisteredMemory<'a, T> {
+    fn get_context(&self) -> Arc<Context> {
+        self.context.clone()
+    }
+}
+
+impl<'a, T: Scalar> Memory for RegisteredMemory<'a, T> {
+    type Elem = T;
+    fn head_addr(&self) -> *const T {
+        self.slice.as_ptr()
+    }
+
+    fn head_addr_mut(&mut self) -> *mut T {
+        self.slice.as_mut_ptr()
+    }
+
+    fn num_elem(&self) -> usize {
+        self.slice.len()
+    }
+
+    fn memory_type(&self) -> MemoryType {
+        MemoryType::Registered
+    }
+
+    fn try_as_slice(&self) -> Option<&[T]> {
+        Some(self.as_slice())
+    }
+
+    fn try_as_mut_slice(&mut self) -> Option<&mut [T]> {
+        Some(self.as_mut_slice())
+    }
+
+    fn try_get_context(&self) -> Option<Arc<Context>> {
+        Some(self.get_context())
+    }
+}
+
+impl<'a, T: Scalar> Continuous for RegisteredMemory<'a, T> {
+    fn as_slice(&self) -> &[T] {
+        self.slice
+    }
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self.slice
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<DeviceMemory<T>> for RegisteredMemory<'a, T> {
+    fn copy_from(&mut self, src: &DeviceMemory<T>) {
+        unsafe { copy_to_host(self, src) }
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<PageLockedMemory<T>> for RegisteredMemory<'a, T> {
+    fn copy_from(&mut self, src: &PageLockedMemory<T>) {
+        unsafe { copy_to_host(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<RegisteredMemory<'_, T>> for DeviceMemory<T> {
+    fn copy_from(&mut self, src: &RegisteredMemory<'_, T>) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<'a, T: Scalar> Memset for RegisteredMemory<'a, T> {
+    fn set(&mut self, value: Self::Elem) {
+        self.slice.iter_mut().for_each(|v| *v = value);
+    }
+}
+
+impl<'a, T> RegisteredMemory<'a, T> {
+    /// Pin `slice` in place, registering it with the CUDA driver.
+    ///
+    /// The returned handle borrows `slice` for `'a`; the buffer must outlive
+    /// the `RegisteredMemory`.
+    pub fn new(context: Arc<Context>, slice: &'a mut [T]) -> Self {
+        unsafe {
+            contexted_call!(
+                &context,
+                cuMemHostRegister_v2,
+                slice.as_mut_ptr() as *mut _,
+                slice.len() * std::mem::size_of::<T>(),
+                0
+            )
+        }
+        .expect("Cannot register host memory");
+        Self { slice, context }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,4 +388,16 @@ mod tests {
         let ctx = device.create_context();
         let _a = DeviceMemory::<i32>::zeros(ctx, 0);
     }
+
+    #[test]
+    fn registered() -> Result<()> {
+        let device = Device::nth(0)?;
+        let ctx = device.create_context();
+        let mut v = vec![0i32; 12];
+        let mut mem = RegisteredMemory::new(ctx, &mut v);
+        assert_eq!(mem.num_elem(), 12);
+        assert_eq!(mem.memory_type(), MemoryType::Registered);
+        mem.as_mut_slice()[0] = 3;
+        Ok(())
+    }
 }
\ No newline at end of file
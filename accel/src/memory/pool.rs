@@ -0,0 +1,339 @@
+//! Suballocating pool for device memory
+//!
+//! Allocating and freeing [DeviceMemory]/[DeviceBuffer] directly means a full
+//! `cuMemAlloc`/`cuMemFree` driver round-trip per call, which serializes a
+//! hot loop that repeatedly allocates small, short-lived buffers. A
+//! [MemoryPool] reserves large slabs up front and hands out sub-blocks from
+//! them instead, returning freed blocks to the pool rather than back to the
+//! driver.
+
+use super::{device::copy_to_device, *};
+use crate::*;
+use cuda::*;
+use std::{marker::PhantomData, sync::Arc, sync::Mutex};
+
+struct Slab {
+    buffer: DeviceBuffer<u8>,
+    /// `true` where the block at that index is free.
+    free: Vec<bool>,
+}
+
+impl Slab {
+    fn new(context: Arc<Context>, blocks: usize, block_size: usize) -> Self {
+        Slab {
+            buffer: unsafe { DeviceBuffer::uninitialized(context, blocks * block_size) },
+            free: vec![true; blocks],
+        }
+    }
+
+    fn is_fully_free(&self) -> bool {
+        self.free.iter().all(|&f| f)
+    }
+
+    /// Find and reserve the first run of `num_blocks` contiguous free blocks.
+    fn reserve(&mut self, num_blocks: usize) -> Option<usize> {
+        let mut run_start = 0;
+        let mut run_len = 0;
+        for (i, &free) in self.free.iter().enumerate() {
+            if free {
+                if run_len == 0 {
+                    run_start = i;
+                }
+                run_len += 1;
+                if run_len == num_blocks {
+                    for slot in &mut self.free[run_start..=i] {
+                        *slot = false;
+                    }
+                    return Some(run_start);
+                }
+            } else {
+                run_len = 0;
+            }
+        }
+        None
+    }
+
+    fn release(&mut self, start_block: usize, num_blocks: usize) {
+        for slot in &mut self.free[start_block..start_block + num_blocks] {
+            *slot = true;
+        }
+    }
+}
+
+struct PoolInner {
+    /// Indexed by stable slab id. A `None` entry is a reclaimed slab; its id
+    /// may be reused by a later slab, which is safe because only fully-free
+    /// slabs are reclaimed, so no live [PoolBlock] still refers to that id.
+    /// A [PoolBlock]'s `slab_id` stays valid when other slabs are reclaimed.
+    slabs: Vec<Option<Slab>>,
+}
+
+/// Device-memory suballocator.
+///
+/// Reserves slabs of `slab_blocks * block_size` bytes and hands out
+/// sub-blocks sized in multiples of `block_size`, amortizing the cost of
+/// `cuMemAlloc`/`cuMemFree` over many short-lived allocations. Dropping a
+/// [PoolBlock] returns its blocks to the pool rather than to the driver; once
+/// the number of wholly-free blocks exceeds `high_water_mark` after a
+/// [PoolBlock] is dropped, fully-free slabs are released back to the driver.
+pub struct MemoryPool {
+    context: Arc<Context>,
+    block_size: usize,
+    slab_blocks: usize,
+    high_water_mark: usize,
+    inner: Mutex<PoolInner>,
+}
+
+/// Every `block_size * start_block` offset must be aligned enough for any
+/// [Scalar] the pool hands out; `u64` (the widest `Scalar` this crate
+/// defines) has the strictest alignment requirement of the bunch.
+const MAX_SCALAR_ALIGN: usize = std::mem::align_of::<u64>();
+
+impl MemoryPool {
+    /// Create a new pool.
+    ///
+    /// - `block_size`: granularity (in bytes) that every allocation is
+    ///   rounded up to. Must be a multiple of [MAX_SCALAR_ALIGN] so that
+    ///   every block's offset from the slab's (driver-aligned) base is
+    ///   itself aligned, whatever `Scalar` type is later allocated into it.
+    /// - `slab_blocks`: number of blocks reserved per slab when the pool
+    ///   needs to grow.
+    /// - `high_water_mark`: number of wholly-free blocks the pool tolerates
+    ///   before reclaiming fully-free slabs back to the driver.
+    pub fn new(
+        context: Arc<Context>,
+        block_size: usize,
+        slab_blocks: usize,
+        high_water_mark: usize,
+    ) -> Arc<Self> {
+        assert!(block_size > 0, "block_size must be positive");
+        assert!(
+            block_size % MAX_SCALAR_ALIGN == 0,
+            "block_size must be a multiple of {} bytes so block offsets stay aligned",
+            MAX_SCALAR_ALIGN
+        );
+        assert!(slab_blocks > 0, "slab_blocks must be positive");
+        Arc::new(MemoryPool {
+            context,
+            block_size,
+            slab_blocks,
+            high_water_mark,
+            inner: Mutex::new(PoolInner { slabs: Vec::new() }),
+        })
+    }
+
+    fn reclaim_if_needed(&self, inner: &mut PoolInner) {
+        let free_blocks: usize = inner
+            .slabs
+            .iter()
+            .flatten()
+            .map(|s| s.free.iter().filter(|&&f| f).count())
+            .sum();
+        if free_blocks <= self.high_water_mark {
+            return;
+        }
+        // Reclaim fully-free slabs in place: `slab_id`s are indices into this
+        // `Vec`, so entries are cleared rather than removed to keep every
+        // live `PoolBlock`'s `slab_id` pointing at the right slab.
+        for slot in &mut inner.slabs {
+            if slot.as_ref().is_some_and(Slab::is_fully_free) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Allocate `size` elements of `T` from the pool, rounding up to the
+    /// pool's block size.
+    pub fn alloc<T: Scalar>(self: &Arc<Self>, size: usize) -> PoolBlock<T> {
+        assert!(size > 0, "Zero-sized pool allocation is forbidden");
+        let bytes = size * std::mem::size_of::<T>();
+        let num_blocks = (bytes + self.block_size - 1) / self.block_size;
+
+        let mut inner = self.inner.lock().expect("Memory pool lock poisoned");
+        for (slab_id, slot) in inner.slabs.iter_mut().enumerate() {
+            if let Some(slab) = slot {
+                if let Some(start_block) = slab.reserve(num_blocks) {
+                    return PoolBlock {
+                        pool: self.clone(),
+                        slab_id,
+                        start_block,
+                        num_blocks,
+                        num_elem: size,
+                        phantom: PhantomData,
+                    };
+                }
+            }
+        }
+
+        let mut slab = Slab::new(
+            self.context.clone(),
+            self.slab_blocks.max(num_blocks),
+            self.block_size,
+        );
+        let start_block = slab
+            .reserve(num_blocks)
+            .expect("Freshly created slab must fit the requested allocation");
+        // Reuse a reclaimed slot if one exists, so the id space doesn't grow
+        // without bound across many reclaim cycles.
+        let slab_id = match inner.slabs.iter().position(|slot| slot.is_none()) {
+            Some(slab_id) => {
+                inner.slabs[slab_id] = Some(slab);
+                slab_id
+            }
+            None => {
+                inner.slabs.push(Some(slab));
+                inner.slabs.len() - 1
+            }
+        };
+        PoolBlock {
+            pool: self.clone(),
+            slab_id,
+            start_block,
+            num_blocks,
+            num_elem: size,
+            phantom: PhantomData,
+        }
+    }
+
+    fn head_ptr(&self, slab_id: usize, start_block: usize) -> *const u8 {
+        let inner = self.inner.lock().expect("Memory pool lock poisoned");
+        let offset = start_block * self.block_size;
+        unsafe {
+            inner.slabs[slab_id]
+                .as_ref()
+                .expect("PoolBlock references a reclaimed slab")
+                .buffer
+                .head_addr()
+                .add(offset)
+        }
+    }
+}
+
+impl Contexted for MemoryPool {
+    fn get_context(&self) -> Arc<Context> {
+        self.context.clone()
+    }
+}
+
+/// A block of device memory suballocated from a [MemoryPool].
+///
+/// Dropping it returns its blocks to the pool instead of calling
+/// `cuMemFree`.
+pub struct PoolBlock<T> {
+    pool: Arc<MemoryPool>,
+    /// Stable id into `MemoryPool::inner.slabs`; unlike a positional index
+    /// into a compacted `Vec`, this never changes when other slabs are
+    /// reclaimed.
+    slab_id: usize,
+    start_block: usize,
+    num_blocks: usize,
+    num_elem: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Drop for PoolBlock<T> {
+    fn drop(&mut self) {
+        let mut inner = self.pool.inner.lock().expect("Memory pool lock poisoned");
+        inner.slabs[self.slab_id]
+            .as_mut()
+            .expect("PoolBlock references a reclaimed slab")
+            .release(self.start_block, self.num_blocks);
+        self.pool.reclaim_if_needed(&mut inner);
+    }
+}
+
+impl<T> Contexted for PoolBlock<T> {
+    fn get_context(&self) -> Arc<Context> {
+        self.pool.get_context()
+    }
+}
+
+impl<T: Scalar> Memory for PoolBlock<T> {
+    type Elem = T;
+    fn head_addr(&self) -> *const T {
+        self.pool.head_ptr(self.slab_id, self.start_block) as *const T
+    }
+
+    fn head_addr_mut(&mut self) -> *mut T {
+        self.pool.head_ptr(self.slab_id, self.start_block) as *mut T
+    }
+
+    fn num_elem(&self) -> usize {
+        self.num_elem
+    }
+
+    fn memory_type(&self) -> MemoryType {
+        MemoryType::Device
+    }
+
+    fn try_as_slice(&self) -> Option<&[T]> {
+        None
+    }
+
+    fn try_as_mut_slice(&mut self) -> Option<&mut [T]> {
+        None
+    }
+
+    fn try_get_context(&self) -> Option<Arc<Context>> {
+        Some(self.get_context())
+    }
+}
+
+impl<T: Scalar> Memcpy<Self> for PoolBlock<T> {
+    fn copy_from(&mut self, src: &Self) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceMemory<T>> for PoolBlock<T> {
+    fn copy_from(&mut self, src: &DeviceMemory<T>) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<PageLockedMemory<T>> for PoolBlock<T> {
+    fn copy_from(&mut self, src: &PageLockedMemory<T>) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<[T]> for PoolBlock<T> {
+    fn copy_from(&mut self, src: &[T]) {
+        unsafe { copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memset for PoolBlock<T> {
+    fn set(&mut self, value: Self::Elem) {
+        // No host-accessible fallback exists for pool-backed blocks. The
+        // driver only offers 1/2/4-byte memset primitives, so widths outside
+        // that set (e.g. f64) fall back to a byte-wise cuMemsetD8_v2 over the
+        // whole allocation -- which is exact whenever every byte of `value`'s
+        // representation is the same, as it always is for `zeros()`. See
+        // `DeviceBuffer::set`, which this mirrors.
+        let elem_size = T::size_of();
+        let ptr = self.head_addr_mut() as _;
+        let size = self.num_elem();
+        let ctx = self.get_context();
+        unsafe {
+            match elem_size {
+                1 => contexted_call!(&ctx, cuMemsetD8_v2, ptr, value.to_le_u8().unwrap(), size),
+                2 => contexted_call!(&ctx, cuMemsetD16_v2, ptr, value.to_le_u16().unwrap(), size),
+                4 => contexted_call!(&ctx, cuMemsetD32_v2, ptr, value.to_le_u32().unwrap(), size),
+                _ => {
+                    let raw =
+                        std::slice::from_raw_parts(&value as *const T as *const u8, elem_size);
+                    let byte = raw[0];
+                    assert!(
+                        raw.iter().all(|b| *b == byte),
+                        "Cannot memset {}-byte elements to a non-uniform byte pattern on a \
+                         non-host-accessible PoolBlock",
+                        elem_size
+                    );
+                    contexted_call!(&ctx, cuMemsetD8_v2, ptr, byte, size * elem_size)
+                }
+            }
+        }
+        .expect("memset failed");
+    }
+}
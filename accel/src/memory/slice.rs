@@ -0,0 +1,254 @@
+//! Borrowed sub-ranges of [DeviceMemory]
+
+use super::{
+    device::{self, memset_device},
+    host, *,
+};
+use crate::*;
+use cuda::*;
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+/// Immutable view into a contiguous sub-range of a [DeviceMemory] allocation.
+///
+/// Borrows its parent allocation for `'a`, so it cannot outlive the
+/// [DeviceMemory] it was sliced from.
+pub struct DeviceSlice<'a, T> {
+    ptr: CUdeviceptr,
+    size: usize,
+    context: Arc<Context>,
+    phantom: PhantomData<&'a T>,
+}
+
+/// Mutable view into a contiguous sub-range of a [DeviceMemory] allocation.
+pub struct DeviceSliceMut<'a, T> {
+    ptr: CUdeviceptr,
+    size: usize,
+    context: Arc<Context>,
+    phantom: PhantomData<&'a mut T>,
+}
+
+fn check_range(len: usize, range: std::ops::Range<usize>) {
+    assert!(
+        range.start <= range.end && range.end <= len,
+        "Out-of-range slice: {:?} for length {}",
+        range,
+        len
+    );
+}
+
+impl<T: Scalar> DeviceMemory<T> {
+    /// Borrow the sub-range `range` of this allocation.
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn slice(&self, range: std::ops::Range<usize>) -> DeviceSlice<'_, T> {
+        check_range(self.size, range.clone());
+        DeviceSlice {
+            ptr: self.ptr + (range.start * std::mem::size_of::<T>()) as CUdeviceptr,
+            size: range.end - range.start,
+            context: self.context.clone(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Mutably borrow the sub-range `range` of this allocation.
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn slice_mut(&mut self, range: std::ops::Range<usize>) -> DeviceSliceMut<'_, T> {
+        check_range(self.size, range.clone());
+        DeviceSliceMut {
+            ptr: self.ptr + (range.start * std::mem::size_of::<T>()) as CUdeviceptr,
+            size: range.end - range.start,
+            context: self.context.clone(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Split this allocation into two mutable, non-overlapping slices at `mid`.
+    ///
+    /// Panics if `mid > self.num_elem()`.
+    pub fn split_at_mut(&mut self, mid: usize) -> (DeviceSliceMut<'_, T>, DeviceSliceMut<'_, T>) {
+        assert!(mid <= self.size, "Out-of-range split point: {}", mid);
+        let elem_size = std::mem::size_of::<T>();
+        let left = DeviceSliceMut {
+            ptr: self.ptr,
+            size: mid,
+            context: self.context.clone(),
+            phantom: PhantomData,
+        };
+        let right = DeviceSliceMut {
+            ptr: self.ptr + (mid * elem_size) as CUdeviceptr,
+            size: self.size - mid,
+            context: self.context.clone(),
+            phantom: PhantomData,
+        };
+        (left, right)
+    }
+}
+
+macro_rules! impl_deref {
+    ($t:ident) => {
+        impl<'a, T> Deref for $t<'a, T> {
+            type Target = [T];
+            fn deref(&self) -> &[T] {
+                unsafe { std::slice::from_raw_parts(self.ptr as _, self.size) }
+            }
+        }
+    };
+}
+impl_deref!(DeviceSlice);
+impl_deref!(DeviceSliceMut);
+
+impl<'a, T> DerefMut for DeviceSliceMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr as _, self.size) }
+    }
+}
+
+macro_rules! impl_contexted {
+    ($t:ident) => {
+        impl<'a, T> Contexted for $t<'a, T> {
+            fn get_context(&self) -> Arc<Context> {
+                self.context.clone()
+            }
+        }
+    };
+}
+impl_contexted!(DeviceSlice);
+impl_contexted!(DeviceSliceMut);
+
+impl<'a, T: Scalar> Memory for DeviceSlice<'a, T> {
+    type Elem = T;
+    fn head_addr(&self) -> *const T {
+        self.ptr as _
+    }
+
+    fn head_addr_mut(&mut self) -> *mut T {
+        self.ptr as _
+    }
+
+    fn num_elem(&self) -> usize {
+        self.size
+    }
+
+    fn memory_type(&self) -> MemoryType {
+        MemoryType::Device
+    }
+
+    fn try_as_slice(&self) -> Option<&[T]> {
+        Some(self)
+    }
+
+    // `DeviceSlice` is a read-only view: report unavailable rather than
+    // reaching for a mutable view that doesn't exist.
+    fn try_as_mut_slice(&mut self) -> Option<&mut [T]> {
+        None
+    }
+
+    fn try_get_context(&self) -> Option<Arc<Context>> {
+        Some(self.get_context())
+    }
+}
+
+impl<'a, T: Scalar> Memory for DeviceSliceMut<'a, T> {
+    type Elem = T;
+    fn head_addr(&self) -> *const T {
+        self.ptr as _
+    }
+
+    fn head_addr_mut(&mut self) -> *mut T {
+        self.ptr as _
+    }
+
+    fn num_elem(&self) -> usize {
+        self.size
+    }
+
+    fn memory_type(&self) -> MemoryType {
+        MemoryType::Device
+    }
+
+    fn try_as_slice(&self) -> Option<&[T]> {
+        Some(self.as_slice())
+    }
+
+    fn try_as_mut_slice(&mut self) -> Option<&mut [T]> {
+        Some(self.as_mut_slice())
+    }
+
+    fn try_get_context(&self) -> Option<Arc<Context>> {
+        Some(self.get_context())
+    }
+}
+
+impl<'a, T: Scalar> Continuous for DeviceSliceMut<'a, T> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<'a, T: Scalar> Memset for DeviceSliceMut<'a, T> {
+    fn set(&mut self, value: Self::Elem) {
+        unsafe { memset_device(self, value).expect("memset failed") };
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<Self> for DeviceSliceMut<'a, T> {
+    fn copy_from(&mut self, src: &Self) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<DeviceSlice<'a, T>> for DeviceSliceMut<'a, T> {
+    fn copy_from(&mut self, src: &DeviceSlice<'a, T>) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<DeviceMemory<T>> for DeviceSliceMut<'a, T> {
+    fn copy_from(&mut self, src: &DeviceMemory<T>) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<PageLockedMemory<T>> for DeviceSliceMut<'a, T> {
+    fn copy_from(&mut self, src: &PageLockedMemory<T>) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<'a, T: Scalar> Memcpy<[T]> for DeviceSliceMut<'a, T> {
+    fn copy_from(&mut self, src: &[T]) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceSlice<'_, T>> for DeviceMemory<T> {
+    fn copy_from(&mut self, src: &DeviceSlice<'_, T>) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceSliceMut<'_, T>> for DeviceMemory<T> {
+    fn copy_from(&mut self, src: &DeviceSliceMut<'_, T>) {
+        unsafe { device::copy_to_device(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceSlice<'_, T>> for PageLockedMemory<T> {
+    fn copy_from(&mut self, src: &DeviceSlice<'_, T>) {
+        unsafe { host::copy_to_host(self, src) }
+    }
+}
+
+impl<T: Scalar> Memcpy<DeviceSliceMut<'_, T>> for PageLockedMemory<T> {
+    fn copy_from(&mut self, src: &DeviceSliceMut<'_, T>) {
+        unsafe { host::copy_to_host(self, src) }
+    }
+}
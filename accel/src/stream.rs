@@ -0,0 +1,85 @@
+//! CUDA stream for asynchronous, stream-ordered execution
+
+use crate::*;
+use cuda::*;
+use std::sync::Arc;
+
+/// CUDA stream used to order asynchronous operations such as memory copies
+/// and kernel launches.
+///
+/// See also [cuStreamCreate] and [cuStreamDestroy].
+///
+/// [cuStreamCreate]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1ga581f0c5833e21ded8b5a56594e243f4
+/// [cuStreamDestroy]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1ga244c8833de4596bcd31a06cdf21ee758
+pub struct Stream {
+    stream: CUstream,
+    context: Arc<Context>,
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe { contexted_call!(self, cuStreamDestroy_v2, self.stream) } {
+            log::error!("Failed to destroy CUDA stream: {:?}", e);
+        }
+    }
+}
+
+impl Contexted for Stream {
+    fn get_context(&self) -> Arc<Context> {
+        self.context.clone()
+    }
+}
+
+impl Stream {
+    /// Create a new stream on the given context.
+    pub fn new(context: Arc<Context>) -> Self {
+        let stream = contexted_new!(
+            &context,
+            cuStreamCreate,
+            CUstream_flags_enum::CU_STREAM_DEFAULT as u32
+        )
+        .expect("Cannot create CUDA stream");
+        Stream { stream, context }
+    }
+
+    /// Block the calling thread until all work queued on this stream has completed.
+    ///
+    /// See also [cuStreamSynchronize].
+    ///
+    /// [cuStreamSynchronize]: https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g15e49dd91ec15991eb7c0a741beb7dad
+    pub fn sync(&self) {
+        unsafe { contexted_call!(self, cuStreamSynchronize, self.stream) }
+            .expect("Failed to synchronize CUDA stream");
+    }
+
+    pub(crate) fn as_raw(&self) -> CUstream {
+        self.stream
+    }
+}
+
+/// Handle for an asynchronous memory copy queued on a [Stream].
+///
+/// The transfer keeps its destination and source borrowed until it is
+/// [`synchronize`](Transfer::synchronize)d or dropped, so the staging buffers
+/// cannot be freed or reused while the copy may still be in flight.
+pub struct Transfer<'a, Dest: ?Sized, Src: ?Sized> {
+    pub(crate) stream: &'a Stream,
+    pub(crate) dest: &'a mut Dest,
+    pub(crate) src: &'a Src,
+}
+
+impl<'a, Dest: ?Sized, Src: ?Sized> Transfer<'a, Dest, Src> {
+    /// Block until the asynchronous copy has completed.
+    pub fn synchronize(self) {
+        self.stream.sync();
+        // The sync above already did `Drop`'s job; skip it so the common
+        // explicit-join path doesn't pay for a redundant second sync.
+        std::mem::forget(self);
+    }
+}
+
+impl<'a, Dest: ?Sized, Src: ?Sized> Drop for Transfer<'a, Dest, Src> {
+    fn drop(&mut self) {
+        self.stream.sync();
+    }
+}